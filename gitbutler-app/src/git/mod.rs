@@ -0,0 +1,5 @@
+mod oid;
+mod refname;
+
+pub use oid::Oid;
+pub use refname::{RemoteRefname, VirtualRefname};
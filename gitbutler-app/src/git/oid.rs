@@ -0,0 +1,63 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+// Oid is a git object id (a SHA-1 hash), kept as raw bytes rather than
+// wrapping `git2::Oid` directly so it can derive `rkyv::Archive` for
+// zero-copy (de)serialization.
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug, PartialEq, Eq, Hash))]
+pub struct Oid([u8; 20]);
+
+impl fmt::Display for Oid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Oid {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 40 {
+            bail!("invalid oid: {} (expected 40 hex characters)", s);
+        }
+        let mut bytes = [0u8; 20];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16)
+                .with_context(|| format!("invalid oid: {}", s))?;
+        }
+        Ok(Oid(bytes))
+    }
+}
+
+impl From<git2::Oid> for Oid {
+    fn from(oid: git2::Oid) -> Self {
+        let mut bytes = [0u8; 20];
+        bytes.copy_from_slice(oid.as_bytes());
+        Oid(bytes)
+    }
+}
+
+impl From<Oid> for git2::Oid {
+    fn from(oid: Oid) -> Self {
+        git2::Oid::from_bytes(&oid.0).expect("Oid always holds 20 valid bytes")
+    }
+}
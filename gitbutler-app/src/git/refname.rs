@@ -0,0 +1,72 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+// RemoteRefname is a parsed `refs/remotes/<remote>/<branch>` reference name.
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Serialize,
+    Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug, PartialEq, Eq))]
+pub struct RemoteRefname {
+    remote: String,
+    branch: String,
+}
+
+impl RemoteRefname {
+    pub fn remote(&self) -> &str {
+        &self.remote
+    }
+
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+}
+
+impl fmt::Display for RemoteRefname {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "refs/remotes/{}/{}", self.remote, self.branch)
+    }
+}
+
+impl FromStr for RemoteRefname {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix("refs/remotes/").unwrap_or(s);
+        let (remote, branch) = s
+            .split_once('/')
+            .with_context(|| format!("invalid remote refname: {}", s))?;
+        Ok(RemoteRefname {
+            remote: remote.to_string(),
+            branch: branch.to_string(),
+        })
+    }
+}
+
+// VirtualRefname is the `refs/gitbutler/<name>` refname a virtual branch is
+// addressed by outside of gitbutler itself, e.g. when referenced from a
+// hook or another git tool.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct VirtualRefname(String);
+
+impl fmt::Display for VirtualRefname {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "refs/gitbutler/{}", self.0)
+    }
+}
+
+impl From<String> for VirtualRefname {
+    fn from(name: String) -> Self {
+        VirtualRefname(name)
+    }
+}
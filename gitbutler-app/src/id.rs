@@ -0,0 +1,63 @@
+use std::{fmt, hash::Hash, marker::PhantomData, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// Id<T> is a typed wrapper around a Uuid, so e.g. a BranchId and some other
+// entity's id can't be accidentally swapped even though they're both just
+// uuids underneath. `T` never appears in an `Id<T>` value, only in its type,
+// so the std traits (Debug, Display, Clone, Copy, PartialEq, Eq, Hash) are
+// implemented by hand below instead of `#[derive(...)]` to avoid spuriously
+// requiring `T: Trait`; only the serde and rkyv traits are derived.
+#[derive(Serialize, Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug, PartialEq, Eq, Hash))]
+pub struct Id<T>(Uuid, #[serde(skip)] PhantomData<T>);
+
+impl<T> Id<T> {
+    pub fn generate() -> Self {
+        Self(Uuid::new_v4(), PhantomData)
+    }
+}
+
+impl<T> fmt::Debug for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<T> fmt::Display for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<T> Clone for Id<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Id<T> {}
+
+impl<T> PartialEq for Id<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T> Eq for Id<T> {}
+
+impl<T> Hash for Id<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<T> FromStr for Id<T> {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.parse()?, PhantomData))
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpStream,
+    process::{Command, Stdio},
+};
+
+use anyhow::{bail, Context, Result};
+
+// sendmail pipes an already-composed RFC-822 message into a local
+// sendmail-compatible binary (e.g. `/usr/sbin/sendmail -t`), the same way
+// `git send-email --sendmail-cmd` delivers mail.
+pub fn sendmail(path: &str, rfc822: &str) -> Result<()> {
+    let mut child = Command::new(path)
+        .arg("-t")
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to spawn sendmail at {}", path))?;
+
+    child
+        .stdin
+        .take()
+        .context("sendmail: child has no stdin")?
+        .write_all(rfc822.as_bytes())
+        .context("failed to write message to sendmail")?;
+
+    let status = child.wait().context("failed to wait for sendmail")?;
+    if !status.success() {
+        bail!("sendmail exited with {status}");
+    }
+    Ok(())
+}
+
+// smtp_send delivers the message over a minimal, unauthenticated SMTP
+// conversation. `url` is `smtp://host:port`; a relay that requires auth or
+// STARTTLS is expected to be reachable as a local trusted relay (e.g.
+// `localhost:25`) rather than have credentials threaded through this URL.
+pub fn smtp_send(url: &str, rfc822: &str) -> Result<()> {
+    let addr = url
+        .strip_prefix("smtp://")
+        .context("smtp url must start with smtp://")?;
+
+    let mut stream =
+        TcpStream::connect(addr).with_context(|| format!("failed to connect to {}", addr))?;
+    let mut reader = BufReader::new(stream.try_clone().context("failed to clone smtp socket")?);
+
+    read_reply(&mut reader)?;
+    command(&mut stream, &mut reader, "EHLO gitbutler\r\n")?;
+
+    let (from, to) = parse_envelope(rfc822)?;
+    command(&mut stream, &mut reader, &format!("MAIL FROM:<{}>\r\n", from))?;
+    for recipient in to {
+        command(&mut stream, &mut reader, &format!("RCPT TO:<{}>\r\n", recipient))?;
+    }
+    command(&mut stream, &mut reader, "DATA\r\n")?;
+
+    // dot-stuff any line that starts with a literal '.' so it isn't mistaken
+    // for the end-of-data marker, and send every line CRLF-terminated.
+    let dot_stuffed = rfc822
+        .lines()
+        .map(|line| {
+            if line.starts_with('.') {
+                format!(".{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\r\n");
+    stream
+        .write_all(dot_stuffed.as_bytes())
+        .context("failed to write message body")?;
+    command(&mut stream, &mut reader, "\r\n.\r\n")?;
+
+    command(&mut stream, &mut reader, "QUIT\r\n")?;
+    Ok(())
+}
+
+fn command(stream: &mut TcpStream, reader: &mut BufReader<TcpStream>, line: &str) -> Result<()> {
+    stream
+        .write_all(line.as_bytes())
+        .with_context(|| format!("failed to send smtp command: {}", line.trim_end()))?;
+    read_reply(reader)
+}
+
+fn read_reply(reader: &mut BufReader<TcpStream>) -> Result<()> {
+    let mut line = String::new();
+    reader.read_line(&mut line).context("failed to read smtp reply")?;
+    match line.chars().next() {
+        Some('2') | Some('3') => Ok(()),
+        _ => bail!("smtp error: {}", line.trim()),
+    }
+}
+
+fn parse_envelope(rfc822: &str) -> Result<(String, Vec<String>)> {
+    let mut from = None;
+    let mut to = Vec::new();
+    for line in rfc822.lines() {
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("From: ") {
+            from = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("To: ") {
+            to = value.split(',').map(|addr| addr.trim().to_string()).collect();
+        }
+    }
+    Ok((from.context("message has no From header")?, to))
+}
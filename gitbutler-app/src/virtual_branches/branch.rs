@@ -1,28 +1,55 @@
+mod bundle;
+mod comment;
 mod file_ownership;
 mod hunk;
 mod ownership;
 mod reader;
+mod signature;
+mod submit;
 mod writer;
 
+pub use bundle::{Bundle, UnbundleError};
+pub use comment::{Comment, Thread};
 pub use file_ownership::FileOwnership;
 pub use hunk::Hunk;
 pub use ownership::Ownership;
 pub use reader::BranchReader as Reader;
+pub use signature::{BranchSignature, Signer, VerifyError, Verifier};
+pub use submit::{submit, BranchSubmitRequest, Transport};
 pub use writer::BranchWriter as Writer;
 
 use serde::{Deserialize, Serialize};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 use crate::{git, id::Id};
 
 pub type BranchId = Id<Branch>;
 
+// the on-disk format of a single branch. bump this whenever the archived
+// layout changes in a way that isn't forward-compatible; `BranchReader` uses
+// it to decide whether it's looking at an archive or the legacy `meta/*`
+// layout.
+const FORMAT_VERSION: u8 = 1;
+
 // this is the struct for the virtual branch data that is stored in our data
 // store. it is more or less equivalent to a git branch reference, but it is not
 // stored or accessible from the git repository itself. it is stored in our
 // session storage under the branches/ directory.
-#[derive(Debug, PartialEq, Clone)]
+//
+// `BranchWriter` persists the whole struct as a single rkyv archive, so every
+// field (including those of `git::Oid`, `Ownership`, `Hunk` and
+// `FileOwnership`) must support zero-copy (de)serialization.
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug, PartialEq))]
 pub struct Branch {
     pub id: BranchId,
     pub name: String,
@@ -40,6 +67,13 @@ pub struct Branch {
     pub ownership: Ownership,
     // order is the number by which UI should sort branches
     pub order: usize,
+    /// detached signature over this branch's identity fields, set by
+    /// `BranchWriter::sign`. `None` for branches that have never been signed.
+    pub signature: Option<BranchSignature>,
+    /// committer time of `head`, refreshed by `BranchWriter::write` whenever
+    /// `head` changes. `None` when `head` has no commit of its own yet, e.g.
+    /// a freshly created branch whose head equals the base.
+    pub last_commit_timestamp_ms: Option<u128>,
 }
 
 impl Branch {
@@ -48,6 +82,49 @@ impl Branch {
     }
 }
 
+// fork_point resolves the commit a branch export (a bundle or a mailed patch
+// series) should start its range just after. a branch that's been pushed
+// has `upstream_head` to anchor on; a purely local branch that's never left
+// this machine - the common case for both bundling and mailing a patch
+// series - has no upstream at all, so it falls back to the repository's
+// configured integration branch instead of failing outright.
+fn fork_point(branch: &Branch, repo: &git::Repository) -> Result<git::Oid> {
+    let target = match branch.upstream_head {
+        Some(upstream_head) => upstream_head,
+        None => repo
+            .target_branch()
+            .context("branch has no upstream and the repository has no configured integration branch to fall back to")?,
+    };
+    repo.merge_base(target, branch.head)
+        .context("failed to compute merge-base")
+}
+
+// OrderBy selects how a list of applied branches should be sorted for
+// display. `Manual` is the hand-maintained `order` field, which goes stale
+// the moment commits arrive from upstream; `RecentActivity` sorts by actual
+// commit activity instead (newest `last_commit_timestamp_ms` first, falling
+// back to `updated_timestamp_ms` for a branch without one).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderBy {
+    #[default]
+    Manual,
+    RecentActivity,
+}
+
+pub fn sort_branches(branches: &mut [Branch], order_by: OrderBy) {
+    match order_by {
+        OrderBy::Manual => branches.sort_by_key(|branch| branch.order),
+        OrderBy::RecentActivity => branches.sort_by_key(|branch| {
+            std::cmp::Reverse(
+                branch
+                    .last_commit_timestamp_ms
+                    .unwrap_or(branch.updated_timestamp_ms),
+            )
+        }),
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct BranchUpdateRequest {
     pub id: BranchId,
@@ -65,6 +142,10 @@ pub struct BranchCreateRequest {
     pub order: Option<usize>,
 }
 
+// legacy path: reconstructs a branch from the pre-archive layout, where
+// every field lived under its own `meta/*` key. `BranchReader::read` only
+// falls back to this when there's no `branch.bin` archive to load; writing
+// the branch again migrates it to the archive format.
 impl TryFrom<&crate::reader::Reader<'_>> for Branch {
     type Error = crate::reader::Error;
 
@@ -147,6 +228,15 @@ impl TryFrom<&crate::reader::Reader<'_>> for Branch {
             )
         })?;
 
+        // a legacy branch predates signing; it simply has none yet.
+        let signature = None;
+
+        let last_commit_timestamp_ms = match reader.read("meta/last_commit_timestamp_ms") {
+            Ok(timestamp) => Some(timestamp.try_into()?),
+            Err(crate::reader::Error::NotFound) => None,
+            Err(e) => return Err(e),
+        };
+
         Ok(Self {
             id,
             name,
@@ -170,6 +260,8 @@ impl TryFrom<&crate::reader::Reader<'_>> for Branch {
             updated_timestamp_ms,
             ownership,
             order,
+            signature,
+            last_commit_timestamp_ms,
         })
     }
 }
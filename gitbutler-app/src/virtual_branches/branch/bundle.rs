@@ -0,0 +1,253 @@
+use std::{
+    env,
+    fs::File,
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+
+use super::{Branch, BranchId, Ownership};
+use crate::{git, id::Id};
+
+const MAGIC: &[u8; 8] = b"GBBNDL01";
+const DIGEST_LEN: usize = 32;
+
+// Header is the fixed-layout prefix of a bundle file: enough to reconstruct
+// a virtual branch on the receiving end, ahead of the packed commit objects.
+struct Header {
+    id: BranchId,
+    name: String,
+    ownership: Ownership,
+    base: git::Oid,
+    tip: git::Oid,
+}
+
+impl Header {
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        write_field(&mut out, self.id.to_string().as_bytes());
+        write_field(&mut out, self.name.as_bytes());
+        write_field(&mut out, self.ownership.to_string().as_bytes());
+        write_field(&mut out, self.base.to_string().as_bytes());
+        write_field(&mut out, self.tip.to_string().as_bytes());
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Result<(Self, usize)> {
+        if bytes.len() < MAGIC.len() || &bytes[..MAGIC.len()] != MAGIC {
+            bail!("not a branch bundle");
+        }
+        let mut cursor = MAGIC.len();
+        let id = read_field(bytes, &mut cursor)?;
+        let name = read_field(bytes, &mut cursor)?;
+        let ownership = read_field(bytes, &mut cursor)?;
+        let base = read_field(bytes, &mut cursor)?;
+        let tip = read_field(bytes, &mut cursor)?;
+
+        Ok((
+            Header {
+                id: id.parse().context("bundle: invalid branch id")?,
+                name,
+                ownership: ownership.parse().context("bundle: invalid ownership")?,
+                base: base.parse().context("bundle: invalid base oid")?,
+                tip: tip.parse().context("bundle: invalid tip oid")?,
+            },
+            cursor,
+        ))
+    }
+}
+
+fn write_field(out: &mut Vec<u8>, field: &[u8]) {
+    out.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    out.extend_from_slice(field);
+}
+
+fn read_field(bytes: &[u8], cursor: &mut usize) -> Result<String> {
+    if bytes.len() < *cursor + 4 {
+        bail!("bundle: truncated header");
+    }
+    let len = u32::from_le_bytes(bytes[*cursor..*cursor + 4].try_into().unwrap()) as usize;
+    *cursor += 4;
+    if bytes.len() < *cursor + len {
+        bail!("bundle: truncated header field");
+    }
+    let field = String::from_utf8(bytes[*cursor..*cursor + len].to_vec())
+        .context("bundle: header field is not utf8")?;
+    *cursor += len;
+    Ok(field)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UnbundleError {
+    #[error("bundle is corrupt or was tampered with")]
+    DigestMismatch,
+    #[error("bundle's base commit {0} is not present locally")]
+    MissingBase(git::Oid),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+// Bundle is a self-contained, portable export of a virtual branch's commit
+// range, for handing a branch to a collaborator who isn't on the same
+// remote. it's a `Header` (enough to reconstruct a virtual branch on the
+// receiving end) followed by the packed git objects and a trailing SHA-256
+// digest over everything that came before it.
+pub struct Bundle;
+
+impl Bundle {
+    // write resolves the branch's fork point (its pushed upstream when it
+    // has one, the repository's integration branch otherwise) and packs the
+    // commits after that point into a self-contained file: header, packed
+    // objects, then a SHA-256 digest of everything written before it.
+    pub fn write(branch: &Branch, repo: &git::Repository) -> Result<File> {
+        let base = super::fork_point(branch, repo)?;
+
+        let header = Header {
+            id: branch.id,
+            name: branch.name.clone(),
+            ownership: branch.ownership.clone(),
+            base,
+            tip: branch.head,
+        };
+
+        let path = env::temp_dir().join(format!("{}.bundle", branch.id));
+        let mut file = File::create(&path).context("failed to create bundle file")?;
+        let mut hasher = Sha256::new();
+
+        let header_bytes = header.encode();
+        hasher.update(&header_bytes);
+        file.write_all(&header_bytes)?;
+
+        let pack = repo
+            .pack_objects(base, branch.head)
+            .context("failed to pack branch commits")?;
+        hasher.update(&pack);
+        file.write_all(&pack)?;
+
+        file.write_all(&hasher.finalize())?;
+        file.flush()?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok(file)
+    }
+
+    // unbundle verifies the trailing digest before trusting any packed
+    // object, then reconstructs a fresh virtual branch (new BranchId,
+    // recomputed timestamps) with the commits landed in local object
+    // storage. a base the repo doesn't have is rejected outright rather than
+    // silently creating commits with a dangling parent.
+    pub fn unbundle(path: &Path, repo: &git::Repository) -> Result<Branch, UnbundleError> {
+        let mut bytes = Vec::new();
+        File::open(path)
+            .context("failed to open bundle file")?
+            .read_to_end(&mut bytes)
+            .context("failed to read bundle file")?;
+
+        if bytes.len() < DIGEST_LEN {
+            return Err(UnbundleError::DigestMismatch);
+        }
+        let (payload, digest) = bytes.split_at(bytes.len() - DIGEST_LEN);
+
+        let mut hasher = Sha256::new();
+        hasher.update(payload);
+        if hasher.finalize().as_slice() != digest {
+            return Err(UnbundleError::DigestMismatch);
+        }
+
+        let (header, header_len) = Header::decode(payload)?;
+
+        if !repo.has_object(header.base) {
+            return Err(UnbundleError::MissingBase(header.base));
+        }
+
+        repo.unpack_objects(&payload[header_len..])
+            .context("failed to unpack branch commits")?;
+
+        let now = crate::time::now_ms();
+
+        let tip_commit = repo
+            .find_commit(header.tip)
+            .context("bundle: tip commit missing after unpack")?;
+
+        Ok(Branch {
+            id: Id::generate(),
+            name: header.name,
+            notes: String::new(),
+            applied: false,
+            upstream: None,
+            upstream_head: None,
+            created_timestamp_ms: now,
+            updated_timestamp_ms: now,
+            tree: tip_commit.tree_id(),
+            head: header.tip,
+            ownership: header.ownership,
+            order: 0,
+            signature: None,
+            last_commit_timestamp_ms: Some(tip_commit.time().seconds() as u128 * 1000),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_header() -> Header {
+        Header {
+            id: BranchId::generate(),
+            name: "feature".to_string(),
+            ownership: Ownership { files: vec![] },
+            base: "000000000000000000000000000000000000000a".parse().unwrap(),
+            tip: "000000000000000000000000000000000000000b".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn header_roundtrips_through_encode_decode() {
+        let header = sample_header();
+        let encoded = header.encode();
+
+        let (decoded, len) = Header::decode(&encoded).unwrap();
+
+        assert_eq!(len, encoded.len());
+        assert_eq!(decoded.id, header.id);
+        assert_eq!(decoded.name, header.name);
+        assert_eq!(decoded.ownership, header.ownership);
+        assert_eq!(decoded.base, header.base);
+        assert_eq!(decoded.tip, header.tip);
+    }
+
+    #[test]
+    fn decode_rejects_missing_magic() {
+        let mut encoded = sample_header().encode();
+        encoded[0] = b'X';
+
+        assert!(Header::decode(&encoded).is_err());
+    }
+
+    #[test]
+    fn decode_rejects_truncated_header() {
+        let encoded = sample_header().encode();
+        let truncated = &encoded[..encoded.len() - 1];
+
+        assert!(Header::decode(truncated).is_err());
+    }
+
+    #[test]
+    fn tampering_with_the_payload_is_detected_by_the_digest() {
+        let payload = sample_header().encode();
+        let mut hasher = Sha256::new();
+        hasher.update(&payload);
+        let digest = hasher.finalize();
+
+        let mut tampered = payload.clone();
+        tampered[8] ^= 0xff;
+        let mut hasher = Sha256::new();
+        hasher.update(&tampered);
+        let tampered_digest = hasher.finalize();
+
+        assert_ne!(digest.as_slice(), tampered_digest.as_slice());
+    }
+}
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+// Comment is one entry in a branch's review thread. `parent_id` links a
+// reply back to the comment it replies to; a `None` parent is a top-level
+// comment on the branch itself.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: usize,
+    pub author: String,
+    pub timestamp_ms: u128,
+    pub parent_id: Option<usize>,
+    pub body: String,
+}
+
+// Thread reconstructs a flat, append-only comment log into parent/child
+// order: each top-level comment immediately followed by its replies,
+// depth-first, in the order they were written.
+#[derive(Debug, Default)]
+pub struct Thread(Vec<Comment>);
+
+impl Thread {
+    pub fn from_log(mut comments: Vec<Comment>) -> Self {
+        comments.sort_by_key(|comment| comment.id);
+
+        // a reply whose parent isn't actually present in the log (a
+        // clobbered comment, a partially-written one) is an orphan, not
+        // noise to discard: surface it as a top-level comment instead of
+        // silently dropping it from the reconstructed thread.
+        let ids: std::collections::HashSet<usize> =
+            comments.iter().map(|comment| comment.id).collect();
+        for comment in &mut comments {
+            if comment.parent_id.is_some_and(|parent_id| !ids.contains(&parent_id)) {
+                comment.parent_id = None;
+            }
+        }
+
+        let mut ordered = Vec::with_capacity(comments.len());
+        append_children(&comments, None, &mut ordered);
+        Thread(ordered)
+    }
+
+    pub fn comments(&self) -> &[Comment] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+fn append_children(all: &[Comment], parent_id: Option<usize>, out: &mut Vec<Comment>) {
+    for comment in all.iter().filter(|comment| comment.parent_id == parent_id) {
+        out.push(comment.clone());
+        append_children(all, Some(comment.id), out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: usize, parent_id: Option<usize>) -> Comment {
+        Comment {
+            id,
+            author: "author".to_string(),
+            timestamp_ms: 0,
+            parent_id,
+            body: format!("comment {}", id),
+        }
+    }
+
+    #[test]
+    fn orders_replies_depth_first_under_their_parent() {
+        let thread = Thread::from_log(vec![
+            comment(0, None),
+            comment(1, None),
+            comment(2, Some(0)),
+            comment(3, Some(2)),
+        ]);
+
+        let ids: Vec<usize> = thread.comments().iter().map(|comment| comment.id).collect();
+        assert_eq!(ids, vec![0, 2, 3, 1]);
+    }
+
+    #[test]
+    fn reparents_orphaned_replies_to_top_level_instead_of_dropping_them() {
+        let thread = Thread::from_log(vec![comment(0, None), comment(1, Some(99))]);
+
+        let ids: Vec<usize> = thread.comments().iter().map(|comment| comment.id).collect();
+        assert_eq!(ids, vec![0, 1]);
+        assert_eq!(thread.comments()[1].parent_id, None);
+    }
+
+    #[test]
+    fn empty_log_is_an_empty_thread() {
+        let thread = Thread::from_log(vec![]);
+
+        assert!(thread.is_empty());
+    }
+}
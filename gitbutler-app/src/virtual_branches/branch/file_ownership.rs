@@ -0,0 +1,78 @@
+use std::{fmt, path::PathBuf, str::FromStr, vec};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::Hunk;
+
+// FileOwnership is the set of hunks within a single file that a branch has
+// claimed for itself. Overlapping hunks between branches are what the
+// "ownership" concept exists to prevent.
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Serialize,
+    Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug, PartialEq, Eq))]
+pub struct FileOwnership {
+    pub file_path: PathBuf,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FileOwnership {
+    pub fn new(file_path: PathBuf, hunks: Vec<Hunk>) -> Self {
+        Self { file_path, hunks }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hunks.is_empty()
+    }
+}
+
+impl FromStr for FileOwnership {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let file_path = parts
+            .next()
+            .with_context(|| format!("failed to parse file ownership: {}", s))?;
+        let hunks = match parts.next() {
+            Some(raw) => raw
+                .split(',')
+                .map(Hunk::from_str)
+                .collect::<Result<vec::Vec<_>>>()?,
+            None => vec![],
+        };
+        Ok(FileOwnership {
+            file_path: file_path.into(),
+            hunks,
+        })
+    }
+}
+
+impl fmt::Display for FileOwnership {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.hunks.is_empty() {
+            write!(f, "{}", self.file_path.display())
+        } else {
+            write!(
+                f,
+                "{}:{}",
+                self.file_path.display(),
+                self.hunks
+                    .iter()
+                    .map(|hunk| hunk.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
+        }
+    }
+}
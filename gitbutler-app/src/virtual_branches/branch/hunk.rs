@@ -0,0 +1,78 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+// Hunk is a line range owned by a branch, expressed in the coordinates of the
+// working directory diff, e.g. "1-10" or "1-10-<sha>" when we also want to
+// pin it to the diff it was taken from.
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Serialize,
+    Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug, PartialEq, Eq))]
+pub struct Hunk {
+    pub start: u32,
+    pub end: u32,
+    pub hash: Option<String>,
+    pub timestamp_ms: Option<u128>,
+}
+
+impl Hunk {
+    pub fn new(start: u32, end: u32, hash: Option<String>, timestamp_ms: Option<u128>) -> Self {
+        Self {
+            start,
+            end,
+            hash,
+            timestamp_ms,
+        }
+    }
+
+    pub fn contains_line(&self, line: u32) -> bool {
+        self.start <= line && line <= self.end
+    }
+}
+
+impl FromStr for Hunk {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('-');
+        let start = parts
+            .next()
+            .with_context(|| format!("failed to parse hunk: {}", s))?
+            .parse::<u32>()
+            .with_context(|| format!("failed to parse hunk start: {}", s))?;
+        let end = parts
+            .next()
+            .with_context(|| format!("failed to parse hunk: {}", s))?
+            .parse::<u32>()
+            .with_context(|| format!("failed to parse hunk end: {}", s))?;
+        let hash = parts.next().map(|s| s.to_string());
+        Ok(Hunk {
+            start,
+            end,
+            hash,
+            timestamp_ms: None,
+        })
+    }
+}
+
+impl fmt::Display for Hunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(hash) = &self.hash {
+            write!(f, "{}-{}-{}", self.start, self.end, hash)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::FileOwnership;
+
+// Ownership is the full set of file ownership claims a branch is making
+// against the working directory, one FileOwnership entry per touched file.
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Default,
+    Serialize,
+    Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug, PartialEq, Eq))]
+pub struct Ownership {
+    pub files: Vec<FileOwnership>,
+}
+
+impl Ownership {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn push(&mut self, ownership: FileOwnership) {
+        self.files.push(ownership);
+    }
+}
+
+impl FromStr for Ownership {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let files = s
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(FileOwnership::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Ownership { files })
+    }
+}
+
+impl fmt::Display for Ownership {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.files
+                .iter()
+                .map(|file| file.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    }
+}
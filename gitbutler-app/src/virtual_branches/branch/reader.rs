@@ -0,0 +1,125 @@
+use super::{comment::Comment, comment::Thread, signature, Branch, BranchId, VerifyError, FORMAT_VERSION};
+use crate::reader;
+
+// the `meta/*` keys a pre-archive branch was scattered across. kept here so
+// BranchWriter can clean them up once a branch has been migrated.
+pub(super) const LEGACY_META_KEYS: &[&str] = &[
+    "meta/name",
+    "meta/notes",
+    "meta/applied",
+    "meta/order",
+    "meta/upstream_head",
+    "meta/upstream",
+    "meta/tree",
+    "meta/head",
+    "meta/created_timestamp_ms",
+    "meta/updated_timestamp_ms",
+    "meta/ownership",
+    "meta/last_commit_timestamp_ms",
+];
+
+pub struct BranchReader<'reader> {
+    reader: &'reader reader::Reader<'reader>,
+}
+
+impl<'reader> BranchReader<'reader> {
+    pub fn new(reader: &'reader reader::Reader<'reader>) -> Self {
+        Self { reader }
+    }
+
+    pub fn read(&self, id: &BranchId) -> Result<Branch, reader::Error> {
+        let reader = self.reader.sub(format!("branches/{}", id));
+        match reader.read("branch.bin") {
+            Ok(reader::Content::Binary(bytes)) => read_archive(&bytes),
+            Ok(_) => Err(reader::Error::Io(
+                std::io::Error::new(std::io::ErrorKind::Other, "branch.bin is not binary").into(),
+            )),
+            Err(reader::Error::NotFound) => Branch::try_from(&reader),
+            Err(e) => Err(e),
+        }
+    }
+
+    // verify checks a branch's detached signature against the given
+    // verifier, so the UI can flag a branch that was tampered with or
+    // authored by someone other than who it claims.
+    pub fn verify(
+        &self,
+        id: &BranchId,
+        verifier: &dyn signature::Verifier,
+    ) -> Result<(), VerifyError> {
+        let branch = self
+            .read(id)
+            .map_err(|_| VerifyError::Missing)?;
+        match &branch.signature {
+            Some(signature) => signature.verify(&branch, verifier),
+            None => Err(VerifyError::Missing),
+        }
+    }
+
+    // comments reconstructs a branch's review thread from the append-only
+    // log under `comments/`. a branch that was never commented on (or
+    // predates comments entirely) has no such directory, which reads back
+    // as an empty thread rather than an error.
+    pub fn comments(&self, id: &BranchId) -> Result<Thread, reader::Error> {
+        let reader = self.reader.sub(format!("branches/{}/comments", id));
+        let entries = match reader.list_files(".") {
+            Ok(entries) => entries,
+            Err(reader::Error::NotFound) => return Ok(Thread::default()),
+            Err(e) => return Err(e),
+        };
+
+        let mut comments = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let raw: String = reader.read(&entry)?.try_into()?;
+            let comment: Comment = serde_json::from_str(&raw).map_err(|e| {
+                reader::Error::Io(
+                    std::io::Error::new(
+                        std::io::ErrorKind::Other,
+                        format!("comments/{}: {}", entry, e),
+                    )
+                    .into(),
+                )
+            })?;
+            comments.push(comment);
+        }
+
+        Ok(Thread::from_log(comments))
+    }
+}
+
+// read_archive validates the archive in place (zero-copy) and then
+// deserializes it into an owned `Branch`. it reads the single-blob format
+// written by `BranchWriter::write`. the leading byte is a format version
+// marker so a future incompatible layout can be detected before we try to
+// interpret the rest of the bytes as an archive.
+fn read_archive(bytes: &[u8]) -> Result<Branch, reader::Error> {
+    let (version, payload) = bytes.split_first().ok_or_else(|| {
+        reader::Error::Io(std::io::Error::new(std::io::ErrorKind::Other, "empty branch archive").into())
+    })?;
+
+    if *version != FORMAT_VERSION {
+        return Err(reader::Error::Io(
+            std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("unsupported branch archive version: {}", version),
+            )
+            .into(),
+        ));
+    }
+
+    let archived = rkyv::check_archived_root::<Branch>(payload).map_err(|e| {
+        reader::Error::Io(
+            std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("corrupt branch archive: {}", e),
+            )
+            .into(),
+        )
+    })?;
+
+    rkyv::Deserialize::deserialize(archived, &mut rkyv::Infallible).map_err(|_: std::convert::Infallible| {
+        reader::Error::Io(
+            std::io::Error::new(std::io::ErrorKind::Other, "failed to deserialize branch archive").into(),
+        )
+    })
+}
@@ -0,0 +1,143 @@
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use super::Branch;
+
+// BranchSignature is a detached signature over the fields that establish a
+// branch's identity. virtual branches never touch a git ref, so there's
+// nothing else binding a branch's head/tree/ownership to the person who
+// wrote them; this lets the UI flag a branch that was tampered with or
+// authored by someone other than who it claims.
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Serialize,
+    Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[archive(check_bytes)]
+#[archive_attr(derive(Debug, PartialEq, Eq))]
+pub struct BranchSignature {
+    /// fingerprint of the key that produced this signature, e.g. an ed25519
+    /// public key or an ssh public key (when gpg.format = ssh), hex-encoded.
+    pub signer: String,
+    pub bytes: Vec<u8>,
+}
+
+// Signer abstracts over the key backend used to produce a BranchSignature,
+// so BranchWriter isn't tied to a single key format (a raw ed25519 keypair,
+// or an ssh-agent/ssh-keygen-backed key when the user has gpg.format = ssh
+// configured for git).
+pub trait Signer {
+    fn public_key(&self) -> String;
+    fn sign(&self, payload: &[u8]) -> anyhow::Result<Vec<u8>>;
+}
+
+// Verifier is the read-side counterpart of Signer.
+pub trait Verifier {
+    fn verify(&self, payload: &[u8], signature: &[u8], signer: &str) -> anyhow::Result<bool>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VerifyError {
+    #[error("branch has no signature")]
+    Missing,
+    #[error("signature does not match branch content")]
+    Invalid,
+}
+
+impl BranchSignature {
+    pub fn verify(&self, branch: &Branch, verifier: &dyn Verifier) -> Result<(), VerifyError> {
+        let payload = canonical_payload(branch);
+        let ok = verifier
+            .verify(&payload, &self.bytes, &self.signer)
+            .unwrap_or(false);
+        if ok {
+            Ok(())
+        } else {
+            Err(VerifyError::Invalid)
+        }
+    }
+}
+
+// canonical_payload serializes the fields that make up a branch's identity
+// as a fixed sequence of `key=value\n` lines, skipping the signature field
+// itself, so signing is deterministic regardless of in-memory field order:
+// re-signing the same content after an edit always reproduces the same
+// bytes, and unrelated fields (name, notes, order, timestamps) don't
+// invalidate a prior signature.
+pub(super) fn canonical_payload(branch: &Branch) -> Vec<u8> {
+    let mut out = String::new();
+    let _ = writeln!(out, "id={}", branch.id);
+    let _ = writeln!(out, "head={}", branch.head);
+    let _ = writeln!(out, "tree={}", branch.tree);
+    let _ = writeln!(out, "ownership={}", branch.ownership);
+    let _ = writeln!(
+        out,
+        "upstream={}",
+        branch
+            .upstream
+            .as_ref()
+            .map(std::string::ToString::to_string)
+            .unwrap_or_default()
+    );
+    out.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virtual_branches::branch::{BranchId, FileOwnership, Hunk, Ownership};
+
+    fn sample_branch() -> Branch {
+        Branch {
+            id: BranchId::generate(),
+            name: "feature".to_string(),
+            notes: String::new(),
+            applied: true,
+            upstream: None,
+            upstream_head: None,
+            created_timestamp_ms: 0,
+            updated_timestamp_ms: 0,
+            tree: "000000000000000000000000000000000000000a".parse().unwrap(),
+            head: "000000000000000000000000000000000000000b".parse().unwrap(),
+            ownership: Ownership {
+                files: vec![FileOwnership::new(
+                    "a.rs".into(),
+                    vec![Hunk::new(1, 2, None, None)],
+                )],
+            },
+            order: 0,
+            signature: None,
+            last_commit_timestamp_ms: None,
+        }
+    }
+
+    #[test]
+    fn canonical_payload_is_unaffected_by_non_identity_fields() {
+        let mut branch = sample_branch();
+        let payload = canonical_payload(&branch);
+
+        branch.name = "renamed".to_string();
+        branch.notes = "some notes".to_string();
+        branch.order = 7;
+        branch.updated_timestamp_ms = 12345;
+
+        assert_eq!(canonical_payload(&branch), payload);
+    }
+
+    #[test]
+    fn canonical_payload_changes_when_an_identity_field_changes() {
+        let mut branch = sample_branch();
+        let payload = canonical_payload(&branch);
+
+        branch.head = "000000000000000000000000000000000000000c".parse().unwrap();
+
+        assert_ne!(canonical_payload(&branch), payload);
+    }
+}
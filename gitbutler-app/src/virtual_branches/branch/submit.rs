@@ -0,0 +1,156 @@
+use std::fmt::Write as _;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::Branch;
+use crate::git;
+
+// Transport describes how a generated patch series is delivered, taking the
+// place of `git send-email`'s own `sendmail`/SMTP split.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Transport {
+    Sendmail { path: String },
+    Smtp { url: String },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BranchSubmitRequest {
+    pub branch_id: super::BranchId,
+    pub to: Vec<String>,
+    pub from: String,
+    pub transport: Transport,
+}
+
+// Message is one RFC-822 email, either the synthesized cover letter or a
+// single commit's patch.
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub message_id: String,
+    pub in_reply_to: Option<String>,
+    pub references: Vec<String>,
+    pub subject: String,
+    pub body: String,
+}
+
+// build_series resolves the branch's fork point (its pushed upstream when
+// it has one, the repository's integration branch otherwise) and turns the
+// commits after that point into an RFC-822 patch series: a synthesized
+// cover letter followed by one message per commit, threaded via
+// In-Reply-To/References off the cover letter, subject `[PATCH n/m]
+// <summary>`. the branch's `upstream` name (when set) is added after `PATCH`
+// in every message's subject tag, cover letter included (e.g.
+// `[PATCH <upstream> 0/m]`), much like `git format-patch --subject-prefix`. the diff in
+// each message is restricted to the branch's `Ownership` hunks, so unowned
+// changes in the working tree never get mailed even though they share the
+// same commit range.
+pub fn build_series(branch: &Branch, repo: &git::Repository) -> Result<Vec<Message>> {
+    let base = super::fork_point(branch, repo)?;
+
+    let commits = repo
+        .commits_between(base, branch.head)
+        .context("failed to walk branch commits")?;
+
+    let cover_id = message_id(&branch.id, 0);
+    let prefix = branch
+        .upstream
+        .as_ref()
+        .map(|u| format!("{} ", u.branch()))
+        .unwrap_or_default();
+
+    let mut messages = Vec::with_capacity(commits.len() + 1);
+    messages.push(Message {
+        message_id: cover_id.clone(),
+        in_reply_to: None,
+        references: vec![],
+        subject: format!("[PATCH {}0/{}] {}", prefix, commits.len(), branch.name),
+        body: format!("{}\n\n{} commit(s) from {}.", branch.name, commits.len(), branch.name),
+    });
+
+    for (i, commit) in commits.iter().enumerate() {
+        let n = i + 1;
+        let diff = repo
+            .diff_commit(commit.id(), &branch.ownership)
+            .context("failed to diff commit against branch ownership")?;
+
+        messages.push(Message {
+            message_id: message_id(&branch.id, n),
+            in_reply_to: Some(cover_id.clone()),
+            references: vec![cover_id.clone()],
+            subject: format!(
+                "[PATCH {}{}/{}] {}",
+                prefix,
+                n,
+                commits.len(),
+                commit.summary()
+            ),
+            body: format!("{}\n---\n{}", commit.message(), diff),
+        });
+    }
+
+    Ok(messages)
+}
+
+fn message_id(branch_id: &super::BranchId, n: usize) -> String {
+    format!("{}-{}@gitbutler", branch_id, n)
+}
+
+// submit builds the series and hands each message to the requested
+// transport, in order, so a threaded mail client reassembles them the same
+// way `git format-patch | git send-email` would.
+pub fn submit(request: &BranchSubmitRequest, branch: &Branch, repo: &git::Repository) -> Result<()> {
+    for message in build_series(branch, repo)? {
+        let rfc822 = render(&message, &request.from, &request.to);
+        deliver(&request.transport, &rfc822)
+            .with_context(|| format!("failed to deliver {}", message.message_id))?;
+    }
+    Ok(())
+}
+
+// strip_header_injection removes embedded CR/LF from a value that's about
+// to be placed on a single RFC-822 header line. a `from`/`to` address, an
+// upstream branch name, or a commit subject containing "\r\n" would
+// otherwise inject arbitrary extra headers (and recipients) into the
+// message once it's handed to sendmail/SMTP.
+fn strip_header_injection(value: &str) -> String {
+    value.chars().filter(|c| *c != '\r' && *c != '\n').collect()
+}
+
+fn render(message: &Message, from: &str, to: &[String]) -> String {
+    let from = strip_header_injection(from);
+    let to = to
+        .iter()
+        .map(|addr| strip_header_injection(addr))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let subject = strip_header_injection(&message.subject);
+
+    let mut out = String::new();
+    let _ = writeln!(out, "Message-Id: <{}>", message.message_id);
+    if let Some(in_reply_to) = &message.in_reply_to {
+        let _ = writeln!(out, "In-Reply-To: <{}>", in_reply_to);
+    }
+    if !message.references.is_empty() {
+        let refs = message
+            .references
+            .iter()
+            .map(|r| format!("<{}>", r))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(out, "References: {}", refs);
+    }
+    let _ = writeln!(out, "From: {}", from);
+    let _ = writeln!(out, "To: {}", to);
+    let _ = writeln!(out, "Subject: {}", subject);
+    let _ = writeln!(out);
+    out.push_str(&message.body);
+    out
+}
+
+fn deliver(transport: &Transport, rfc822: &str) -> Result<()> {
+    match transport {
+        Transport::Sendmail { path } => crate::mail::sendmail(path, rfc822),
+        Transport::Smtp { url } => crate::mail::smtp_send(url, rfc822),
+    }
+}
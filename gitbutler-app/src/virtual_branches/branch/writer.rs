@@ -0,0 +1,130 @@
+use anyhow::{Context, Result};
+
+use super::{comment::Comment, reader::LEGACY_META_KEYS, signature, Branch, BranchId, FORMAT_VERSION};
+use crate::{git, reader, writer};
+
+pub struct BranchWriter<'writer> {
+    writer: &'writer writer::DirWriter,
+    repo: &'writer git::Repository,
+}
+
+impl<'writer> BranchWriter<'writer> {
+    pub fn new(writer: &'writer writer::DirWriter, repo: &'writer git::Repository) -> Self {
+        Self { writer, repo }
+    }
+
+    // write serializes the whole branch as a single rkyv archive, prefixed
+    // with a one-byte format version, and replaces whatever `meta/*` keys a
+    // pre-archive branch left behind. this is also how a legacy branch gets
+    // migrated: the first time it's written again it's written whole, and
+    // the scattered keys are removed so a stale one can't resurrect itself.
+    //
+    // `last_commit_timestamp_ms` is refreshed here from `head` every time,
+    // so it never needs its own explicit "did head change" bookkeeping.
+    pub fn write(&self, branch: &mut Branch) -> Result<()> {
+        branch.last_commit_timestamp_ms = last_commit_timestamp_ms(self.repo, branch.head);
+
+        let dir = format!("branches/{}", branch.id);
+
+        let bytes = rkyv::to_bytes::<_, 4096>(branch).context("failed to archive branch")?;
+
+        let mut payload = Vec::with_capacity(bytes.len() + 1);
+        payload.push(FORMAT_VERSION);
+        payload.extend_from_slice(&bytes);
+
+        self.writer
+            .write_bytes(&format!("{}/branch.bin", dir), &payload)
+            .context("failed to write branch archive")?;
+
+        self.remove_legacy(&dir)?;
+
+        Ok(())
+    }
+
+    // sign canonicalizes the branch's identity fields (id, head, tree,
+    // ownership, upstream) and replaces `branch.signature` with a fresh
+    // signature over them. the canonical payload is deterministic regardless
+    // of field order, so re-signing after an edit to those fields is
+    // reproducible; callers still need to `write` the branch to persist it.
+    pub fn sign(&self, branch: &mut Branch, signer: &dyn signature::Signer) -> Result<()> {
+        let payload = signature::canonical_payload(branch);
+        let bytes = signer
+            .sign(&payload)
+            .context("failed to sign branch identity")?;
+        branch.signature = Some(signature::BranchSignature {
+            signer: signer.public_key(),
+            bytes,
+        });
+        Ok(())
+    }
+
+    // add_comment appends a comment to the branch's review thread, assigning
+    // it a monotonic id (one past the highest id already in the log) and
+    // timestamping it. the log is append-only: existing comments are never
+    // rewritten. the initial id comes from a listing snapshot, which two
+    // concurrent callers could race on, so the write itself is
+    // create-exclusive: a collision (someone else claimed that id first)
+    // retries with the next one instead of silently clobbering their
+    // comment.
+    pub fn add_comment(
+        &self,
+        reader: &reader::Reader,
+        branch_id: &BranchId,
+        author: String,
+        parent_id: Option<usize>,
+        body: String,
+    ) -> Result<Comment> {
+        let dir = format!("branches/{}/comments", branch_id);
+
+        let mut next_id = reader
+            .sub(&dir)
+            .list_files(".")
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|name| name.parse::<usize>().ok())
+            .max()
+            .map_or(0, |max| max + 1);
+
+        loop {
+            let comment = Comment {
+                id: next_id,
+                author: author.clone(),
+                timestamp_ms: crate::time::now_ms(),
+                parent_id,
+                body: body.clone(),
+            };
+
+            let raw = serde_json::to_string(&comment).context("failed to serialize comment")?;
+            match self
+                .writer
+                .write_string_create_new(&format!("{}/{}", dir, comment.id), &raw)
+            {
+                Ok(()) => return Ok(comment),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    next_id += 1;
+                }
+                Err(e) => return Err(e).context("failed to write comment"),
+            }
+        }
+    }
+
+    fn remove_legacy(&self, dir: &str) -> Result<()> {
+        for key in LEGACY_META_KEYS {
+            // best-effort: a branch written for the first time in the new
+            // format never had these keys to begin with.
+            self.writer.remove(&format!("{}/{}", dir, key)).ok();
+        }
+        Ok(())
+    }
+}
+
+// last_commit_timestamp_ms looks up the committer time of `head`. a freshly
+// created branch whose head is just the base commit (or any head that's
+// since disappeared from the object store) yields `None` rather than an
+// error, since "no commit activity yet" is an expected, not exceptional,
+// state.
+fn last_commit_timestamp_ms(repo: &git::Repository, head: git::Oid) -> Option<u128> {
+    repo.find_commit(head)
+        .ok()
+        .map(|commit| commit.time().seconds() as u128 * 1000)
+}
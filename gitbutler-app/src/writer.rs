@@ -0,0 +1,50 @@
+use std::{
+    fs,
+    io::{self, Write as _},
+    path::PathBuf,
+};
+
+// DirWriter writes session files relative to a root directory, creating
+// parent directories as needed. it's the write-side counterpart to
+// `reader::Reader`.
+pub struct DirWriter {
+    root: PathBuf,
+}
+
+impl DirWriter {
+    pub fn open(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub fn write_string(&self, path: &str, contents: &str) -> io::Result<()> {
+        self.write_bytes(path, contents.as_bytes())
+    }
+
+    pub fn write_bytes(&self, path: &str, contents: &[u8]) -> io::Result<()> {
+        let path = self.root.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)
+    }
+
+    // write_string_create_new writes `contents` to `path`, failing with
+    // `io::ErrorKind::AlreadyExists` instead of overwriting if the file is
+    // already there. used wherever a caller needs to claim a path
+    // atomically, e.g. an append-only log keyed by id.
+    pub fn write_string_create_new(&self, path: &str, contents: &str) -> io::Result<()> {
+        let path = self.root.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)?;
+        file.write_all(contents.as_bytes())
+    }
+
+    pub fn remove(&self, path: &str) -> io::Result<()> {
+        fs::remove_file(self.root.join(path))
+    }
+}